@@ -1,32 +1,118 @@
 pub mod aws_profile_credential;
+pub mod auto_refresh;
+pub(crate) mod assume_role;
+pub(crate) mod credential_process;
+pub(crate) mod ini_parser;
+pub(crate) mod sso;
 
 use std::collections::HashMap;
 use std::fs::{self};
-use std::io::BufRead;
 use std::path::Path;
 
 use rusoto_credential::{AwsCredentials, CredentialsError};
 
-use crate::file::create_file_reader_for;
+use crate::file::credential::auto_refresh::AutoRefreshingProfileCredentials;
 use crate::file::credential::aws_profile_credential::AwsProfileCredential;
-use crate::file::helper::line::{extract_value_from, is_comment_or_empty};
-use crate::file::helper::line::{get_profile_name_from, is_profile};
+use crate::file::credential::ini_parser::parse_ini_sections;
 
 pub fn parse_credentials_file(
     credential_file_path: &Path,
 ) -> Result<HashMap<String, AwsCredentials>, CredentialsError> {
-    match is_valid_file_path(credential_file_path) {
-        Ok(_) => {
-            let profile_credentials_map = create_profile_credentials_map_from(credential_file_path);
+    is_valid_file_path(credential_file_path)?;
 
-            if profile_credentials_map.is_empty() {
-                return Err(CredentialsError::new("No credentials found."));
-            }
+    let profile_credentials_map = create_profile_credentials_map_from(credential_file_path)?;
 
-            Ok(profile_credentials_map)
-        }
-        Err(e) => Err(e),
+    if profile_credentials_map.is_empty() {
+        return Err(CredentialsError::new("No credentials found."));
+    }
+
+    Ok(profile_credentials_map)
+}
+
+/// Loads the full credential-resolution pipeline for `credential_file_path`:
+/// static keys from the credentials file, plus an `AutoRefreshingProfileCredentials`
+/// over them that transparently resolves assume-role, `credential_process`
+/// and AWS SSO profiles the first time each is actually selected, and again
+/// once their credentials near expiration. From the credentials file itself
+/// only `credential_process` settings and nested `sso_session` blocks are
+/// taken; `role_arn`/`source_profile` and `sso_*` profile keys there are not
+/// read, so such profiles resolve to their anonymous placeholder unless
+/// `profile_configs` defines them. No dynamic profile, including SSO, is ever
+/// resolved until `credentials_for` is called for it.
+///
+/// This, not `parse_credentials_file`, is the entry point profile selection
+/// should call: `parse_credentials_file` alone only ever surfaces static keys
+/// and anonymous placeholders for everything else.
+pub fn load_credentials_with_auto_refresh(
+    credential_file_path: &Path,
+    profile_configs: &HashMap<String, HashMap<String, String>>,
+    sso_sessions: &HashMap<String, HashMap<String, String>>,
+) -> Result<AutoRefreshingProfileCredentials, CredentialsError> {
+    let static_credentials = parse_credentials_file(credential_file_path)?;
+
+    let credential_processes = credential_process_commands_from(credential_file_path)?;
+    let profile_configs = merge_credential_processes(profile_configs, credential_processes);
+
+    let mut sso_sessions = sso_sessions.clone();
+    sso_sessions.extend(sso_sessions_from_credentials_file(credential_file_path)?);
+
+    Ok(AutoRefreshingProfileCredentials::new(
+        static_credentials,
+        &profile_configs,
+        &sso_sessions,
+    ))
+}
+
+/// Inline `sso_session`-style nested subsections declared directly in the
+/// credentials file (see `ProfileSection::nested`), keyed by session name, so
+/// they stand in for a separate `[sso-session <name>]` config section when
+/// resolving an `sso_session` reference.
+fn sso_sessions_from_credentials_file(
+    credential_file_path: &Path,
+) -> Result<HashMap<String, HashMap<String, String>>, CredentialsError> {
+    let sections = parse_ini_sections(credential_file_path)?;
+
+    Ok(sections.into_values().flat_map(|section| section.nested).collect())
+}
+
+/// Profiles in the credentials file that define `credential_process` but no
+/// inline keys, keyed by profile name. `AwsProfileCredential::into_aws_credential`
+/// deliberately does not run these during the static parse pass (see
+/// chunk0-6), so they are surfaced here instead, to be merged alongside
+/// config-file-sourced `role_arn`/`credential_process` settings and resolved
+/// lazily by `AutoRefreshingProfileCredentials`.
+fn credential_process_commands_from(
+    credential_file_path: &Path,
+) -> Result<HashMap<String, String>, CredentialsError> {
+    let sections = parse_ini_sections(credential_file_path)?;
+
+    Ok(sections
+        .into_iter()
+        .filter(|(_, section)| !section.values.contains_key("aws_access_key_id"))
+        .filter_map(|(profile_name, section)| {
+            section
+                .values
+                .get("credential_process")
+                .map(|command| (profile_name, command.clone()))
+        })
+        .collect())
+}
+
+fn merge_credential_processes(
+    profile_configs: &HashMap<String, HashMap<String, String>>,
+    credential_processes: HashMap<String, String>,
+) -> HashMap<String, HashMap<String, String>> {
+    let mut merged_configs = profile_configs.clone();
+
+    for (profile_name, command) in credential_processes {
+        merged_configs
+            .entry(profile_name)
+            .or_default()
+            .entry("credential_process".to_string())
+            .or_insert(command);
     }
+
+    merged_configs
 }
 
 fn is_valid_file_path(credential_file_path: &Path) -> Result<(), CredentialsError> {
@@ -52,85 +138,37 @@ fn is_valid_file_path(credential_file_path: &Path) -> Result<(), CredentialsErro
 
 fn create_profile_credentials_map_from(
     credential_file_path: &Path,
-) -> HashMap<String, AwsCredentials> {
-    let credential_file_reader = create_file_reader_for(credential_file_path);
-
-    let mut profile_credentials_map: HashMap<String, AwsCredentials> = HashMap::new();
-    let mut aws_profile_credential = AwsProfileCredential::new();
-
-    for (line_no, line) in credential_file_reader.lines().enumerate() {
-        let unwrapped_line: String =
-            line.unwrap_or_else(|_| panic!("Failed to read credentials file, line: {}", line_no));
-
-        if is_comment_or_empty(&unwrapped_line) {
-            continue;
-        }
-
-        if is_profile(&unwrapped_line) {
-            profile_credentials_map =
-                try_insert_profile_credential_to(profile_credentials_map, aws_profile_credential);
-
-            aws_profile_credential = AwsProfileCredential::new_with_profile_name(
-                get_profile_name_from(&unwrapped_line)
-                    .unwrap_or_else(|| panic!("Cannot get profile name, line: {}", line_no)),
-            );
-        } else {
-            aws_profile_credential =
-                try_assign_aws_profile_credential_from(&unwrapped_line, aws_profile_credential);
-        }
-    }
-
-    profile_credentials_map =
-        try_insert_profile_credential_to(profile_credentials_map, aws_profile_credential);
-
-    profile_credentials_map
-}
-
-fn try_assign_aws_profile_credential_from(
-    line: &str,
-    mut aws_profile_credential: AwsProfileCredential,
-) -> AwsProfileCredential {
-    let lower_case_line = line.to_ascii_lowercase();
-
-    if is_aws_access_key(&lower_case_line) && aws_profile_credential.access_key.is_none() {
-        aws_profile_credential.access_key = extract_value_from(&lower_case_line);
-    } else if is_aws_secret_key(&lower_case_line) && aws_profile_credential.secret_key.is_none() {
-        aws_profile_credential.secret_key = extract_value_from(&lower_case_line);
-    } else if is_aws_token(&lower_case_line) && aws_profile_credential.token.is_none() {
-        aws_profile_credential.token = extract_value_from(&lower_case_line);
-    }
-
-    aws_profile_credential
-}
-
-fn is_aws_access_key(line: &str) -> bool {
-    line.contains("aws_access_key_id")
-}
-
-fn is_aws_secret_key(line: &str) -> bool {
-    line.contains("aws_secret_access_key")
-}
-
-fn is_aws_token(line: &str) -> bool {
-    line.contains("aws_session_token") || line.contains("aws_security_token")
-}
-
-fn try_insert_profile_credential_to(
-    mut profile_credentials_map: HashMap<String, AwsCredentials>,
-    aws_profile_credential: AwsProfileCredential,
-) -> HashMap<String, AwsCredentials> {
-    if let (Some(profile_name), Some(aws_credential)) = (
-        aws_profile_credential.profile_name.clone(),
-        aws_profile_credential.into_aws_credential(),
-    ) {
-        profile_credentials_map.insert(profile_name, aws_credential);
+) -> Result<HashMap<String, AwsCredentials>, CredentialsError> {
+    let sections = parse_ini_sections(credential_file_path)?;
+
+    let mut profile_credentials_map = HashMap::new();
+
+    for (profile_name, section) in sections {
+        let aws_profile_credential = AwsProfileCredential {
+            access_key: section.values.get("aws_access_key_id").cloned(),
+            secret_key: section.values.get("aws_secret_access_key").cloned(),
+            token: section
+                .values
+                .get("aws_session_token")
+                .or_else(|| section.values.get("aws_security_token"))
+                .cloned(),
+            ..AwsProfileCredential::new_with_profile_name(profile_name.clone())
+        };
+
+        // Every profile survives into the map, even one with only a region or
+        // an unresolved credential_process/role_arn: `into_aws_credential`
+        // never returns `None`, it falls back to an anonymous placeholder
+        // (see `is_anonymous`) so later resolution passes can still find it
+        // by profile name instead of it being silently dropped here.
+        profile_credentials_map.insert(profile_name, aws_profile_credential.into_aws_credential());
     }
 
-    profile_credentials_map
+    Ok(profile_credentials_map)
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
     use std::path::Path;
 
     use crate::file::config::create_profile_config_map_from;
@@ -211,4 +249,54 @@ mod tests {
         assert_eq!(default_profile.aws_access_key_id(), "foo");
         assert_eq!(default_profile.aws_secret_access_key(), "bar");
     }
+
+    #[test]
+    fn load_credentials_with_auto_refresh_serves_static_profile() {
+        let provider = super::load_credentials_with_auto_refresh(
+            Path::new("tests/sample-data/default_profile_credentials"),
+            &HashMap::new(),
+            &HashMap::new(),
+        )
+        .expect("Cannot load credentials with auto refresh");
+
+        let credentials = provider
+            .credentials_for(DEFAULT)
+            .expect("No default profile in default_profile_credentials");
+        assert_eq!(credentials.aws_access_key_id(), "foo");
+    }
+
+    #[test]
+    fn load_credentials_with_auto_refresh_resolves_credential_process_profile() {
+        // Proves the pipeline actually wires a dynamic profile through to
+        // resolution, not just a profile with inline static keys: this
+        // profile has no aws_access_key_id/aws_secret_access_key at all, only
+        // a credential_process, so a correct result depends on
+        // credential_process_commands_from surfacing it and
+        // AutoRefreshingProfileCredentials resolving it lazily.
+        let provider = super::load_credentials_with_auto_refresh(
+            Path::new("tests/sample-data/credential_process_only_credentials"),
+            &HashMap::new(),
+            &HashMap::new(),
+        )
+        .expect("Cannot load credentials with auto refresh");
+
+        let credentials = provider
+            .credentials_for(DEFAULT)
+            .expect("Cannot resolve credential_process profile");
+        assert_eq!(credentials.aws_access_key_id(), "foo");
+        assert_eq!(credentials.aws_secret_access_key(), "bar");
+    }
+
+    #[test]
+    fn sso_sessions_from_credentials_file_reads_nested_sso_session_blocks() {
+        let sso_sessions = super::sso_sessions_from_credentials_file(Path::new(
+            "tests/sample-data/nested_sso_session_credentials",
+        ))
+        .expect("Cannot read nested sso_session blocks");
+
+        let session = sso_sessions
+            .get("my-sso")
+            .expect("No my-sso nested sso_session block");
+        assert_eq!(session.get("sso_region"), Some(&"us-east-1".to_string()));
+    }
 }
@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use rusoto_core::{HttpClient, Region};
+use rusoto_credential::{AwsCredentials, CredentialsError, StaticProvider};
+use rusoto_sts::{AssumeRoleRequest, Sts, StsClient};
+
+/// The `role_arn`/`source_profile` (and friends) settings of a profile that
+/// assumes a role rather than carrying static keys.
+#[derive(Debug, Clone, Default)]
+pub struct RoleAssumption {
+    pub role_arn: String,
+    pub source_profile: String,
+    pub mfa_serial: Option<String>,
+    pub external_id: Option<String>,
+    pub role_session_name: Option<String>,
+    pub duration_seconds: Option<i64>,
+}
+
+/// Resolves every profile in `role_assumptions` to temporary `AwsCredentials`,
+/// following `source_profile` chains transitively and rejecting cycles.
+pub fn resolve_role_assumption_chains(
+    role_assumptions: &HashMap<String, RoleAssumption>,
+    static_credentials: &HashMap<String, AwsCredentials>,
+) -> Result<HashMap<String, AwsCredentials>, CredentialsError> {
+    let mut resolved = HashMap::new();
+
+    for profile_name in role_assumptions.keys() {
+        resolve_profile(
+            profile_name,
+            role_assumptions,
+            static_credentials,
+            &mut resolved,
+            &mut Vec::new(),
+        )?;
+    }
+
+    Ok(resolved)
+}
+
+fn resolve_profile(
+    profile_name: &str,
+    role_assumptions: &HashMap<String, RoleAssumption>,
+    static_credentials: &HashMap<String, AwsCredentials>,
+    resolved: &mut HashMap<String, AwsCredentials>,
+    visiting: &mut Vec<String>,
+) -> Result<AwsCredentials, CredentialsError> {
+    if let Some(credentials) = resolved.get(profile_name) {
+        return Ok(credentials.clone());
+    }
+
+    let assumption = match role_assumptions.get(profile_name) {
+        Some(assumption) => assumption,
+        None => {
+            return static_credentials.get(profile_name).cloned().ok_or_else(|| {
+                CredentialsError::new(format!(
+                    "source_profile [ {} ] has no static credentials.",
+                    profile_name
+                ))
+            })
+        }
+    };
+
+    if visiting.contains(&profile_name.to_string()) {
+        return Err(CredentialsError::new(format!(
+            "Cycle detected while resolving source_profile chain at [ {} ].",
+            profile_name
+        )));
+    }
+    visiting.push(profile_name.to_string());
+
+    let source_credentials = resolve_profile(
+        &assumption.source_profile,
+        role_assumptions,
+        static_credentials,
+        resolved,
+        visiting,
+    )?;
+
+    visiting.pop();
+
+    let assumed = assume_role(&source_credentials, assumption)?;
+    resolved.insert(profile_name.to_string(), assumed.clone());
+
+    Ok(assumed)
+}
+
+fn assume_role(
+    source_credentials: &AwsCredentials,
+    assumption: &RoleAssumption,
+) -> Result<AwsCredentials, CredentialsError> {
+    let provider = StaticProvider::new(
+        source_credentials.aws_access_key_id().to_string(),
+        source_credentials.aws_secret_access_key().to_string(),
+        source_credentials.token().clone(),
+        None,
+    );
+
+    let http_client = HttpClient::new()
+        .map_err(|e| CredentialsError::new(format!("Cannot create HTTP client: {}", e)))?;
+    let client = StsClient::new_with(http_client, provider, Region::default());
+
+    let request = AssumeRoleRequest {
+        role_arn: assumption.role_arn.clone(),
+        role_session_name: assumption
+            .role_session_name
+            .clone()
+            .unwrap_or_else(|| "awsp".to_string()),
+        serial_number: assumption.mfa_serial.clone(),
+        external_id: assumption.external_id.clone(),
+        duration_seconds: assumption.duration_seconds,
+        ..Default::default()
+    };
+
+    let runtime = tokio::runtime::Runtime::new()
+        .map_err(|e| CredentialsError::new(format!("Cannot create async runtime: {}", e)))?;
+
+    let response = runtime
+        .block_on(client.assume_role(request))
+        .map_err(|e| CredentialsError::new(format!("AssumeRole failed for [ {} ]: {}", assumption.role_arn, e)))?;
+
+    let credentials = response
+        .credentials
+        .ok_or_else(|| CredentialsError::new("AssumeRole response did not contain credentials."))?;
+
+    let expiration = DateTime::parse_from_rfc3339(&credentials.expiration)
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok();
+
+    Ok(AwsCredentials::new(
+        credentials.access_key_id,
+        credentials.secret_access_key,
+        Some(credentials.session_token),
+        expiration,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn credentials(access_key: &str) -> AwsCredentials {
+        AwsCredentials::new(access_key, "secret", None, None)
+    }
+
+    #[test]
+    fn resolve_role_assumption_chains_detects_cycles() {
+        let mut role_assumptions = HashMap::new();
+        role_assumptions.insert(
+            "a".to_string(),
+            RoleAssumption {
+                role_arn: "arn:aws:iam::111111111111:role/a".to_string(),
+                source_profile: "b".to_string(),
+                ..RoleAssumption::default()
+            },
+        );
+        role_assumptions.insert(
+            "b".to_string(),
+            RoleAssumption {
+                role_arn: "arn:aws:iam::111111111111:role/b".to_string(),
+                source_profile: "a".to_string(),
+                ..RoleAssumption::default()
+            },
+        );
+
+        let result = resolve_role_assumption_chains(&role_assumptions, &HashMap::new());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn resolve_role_assumption_chains_errors_on_missing_source() {
+        let mut role_assumptions = HashMap::new();
+        role_assumptions.insert(
+            "a".to_string(),
+            RoleAssumption {
+                role_arn: "arn:aws:iam::111111111111:role/a".to_string(),
+                source_profile: "missing".to_string(),
+                ..RoleAssumption::default()
+            },
+        );
+
+        let result = resolve_role_assumption_chains(&role_assumptions, &HashMap::new());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn resolve_profile_returns_static_credentials_directly() {
+        let mut static_credentials = HashMap::new();
+        static_credentials.insert("base".to_string(), credentials("base_access_key"));
+
+        let mut resolved = HashMap::new();
+        let result = resolve_profile(
+            "base",
+            &HashMap::new(),
+            &static_credentials,
+            &mut resolved,
+            &mut Vec::new(),
+        );
+
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().aws_access_key_id(), "base_access_key");
+    }
+}
@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use chrono::{Duration as ChronoDuration, Utc};
+use rusoto_credential::{AwsCredentials, CredentialsError};
+
+use crate::file::credential::assume_role::{resolve_role_assumption_chains, RoleAssumption};
+use crate::file::credential::aws_profile_credential::is_anonymous;
+use crate::file::credential::credential_process::resolve_credential_process;
+use crate::file::credential::sso::{extract_sso_profile, resolve_sso_profile, SsoProfile};
+
+const EXPIRATION_SKEW_SECONDS: i64 = 5 * 60;
+
+/// How a profile's dynamic credentials are re-resolved once they approach
+/// expiration.
+enum DynamicSource {
+    CredentialProcess(String),
+    RoleAssumption(RoleAssumption),
+    Sso(SsoProfile),
+}
+
+/// Caches resolved credentials per profile and transparently re-resolves any
+/// profile that is within `EXPIRATION_SKEW_SECONDS` of its `Expiration`,
+/// mirroring rusoto's `AutoRefreshingProvider`.
+pub struct AutoRefreshingProfileCredentials {
+    static_credentials: HashMap<String, AwsCredentials>,
+    dynamic_sources: HashMap<String, DynamicSource>,
+    role_assumptions: HashMap<String, RoleAssumption>,
+    cache: Mutex<HashMap<String, AwsCredentials>>,
+}
+
+impl AutoRefreshingProfileCredentials {
+    pub fn new(
+        static_credentials: HashMap<String, AwsCredentials>,
+        profile_configs: &HashMap<String, HashMap<String, String>>,
+        sso_sessions: &HashMap<String, HashMap<String, String>>,
+    ) -> Self {
+        let dynamic_sources = extract_dynamic_sources(profile_configs, sso_sessions);
+
+        // Kept alongside dynamic_sources so `resolve` can follow the selected
+        // profile's source_profile chain through the *whole* set of role
+        // assumptions, not just the one profile being resolved — a chained
+        // source_profile that is itself a role assumption needs to be found
+        // there too.
+        let role_assumptions = dynamic_sources
+            .iter()
+            .filter_map(|(profile_name, source)| match source {
+                DynamicSource::RoleAssumption(assumption) => Some((profile_name.clone(), assumption.clone())),
+                _ => None,
+            })
+            .collect();
+
+        AutoRefreshingProfileCredentials {
+            dynamic_sources,
+            role_assumptions,
+            static_credentials,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns cached credentials for `profile_name`, transparently
+    /// re-resolving them first if they are at or near expiration.
+    pub fn credentials_for(&self, profile_name: &str) -> Result<AwsCredentials, CredentialsError> {
+        {
+            let cache = self.cache.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            if let Some(credentials) = cache.get(profile_name) {
+                if !is_near_expiry(credentials) {
+                    return Ok(credentials.clone());
+                }
+            }
+        }
+
+        let credentials = self.resolve(profile_name)?;
+
+        let mut cache = self.cache.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        cache.insert(profile_name.to_string(), credentials.clone());
+
+        Ok(credentials)
+    }
+
+    fn resolve(&self, profile_name: &str) -> Result<AwsCredentials, CredentialsError> {
+        match self.dynamic_sources.get(profile_name) {
+            Some(DynamicSource::CredentialProcess(command)) => resolve_credential_process(command),
+            Some(DynamicSource::Sso(sso_profile)) => resolve_sso_profile(sso_profile),
+            Some(DynamicSource::RoleAssumption(_)) => {
+                // Only the selected profile's own source_profile chain is
+                // resolved; a repeated profile ends the walk so that
+                // resolve_role_assumption_chains still reports the cycle.
+                let mut chain = HashMap::new();
+                let mut next = Some(profile_name);
+                while let Some(name) = next {
+                    next = match self.role_assumptions.get(name) {
+                        Some(assumption) if !chain.contains_key(name) => {
+                            chain.insert(name.to_string(), assumption.clone());
+                            Some(assumption.source_profile.as_str())
+                        }
+                        _ => None,
+                    };
+                }
+
+                let resolved = resolve_role_assumption_chains(&chain, &self.static_credentials)?;
+
+                resolved.get(profile_name).cloned().ok_or_else(|| {
+                    CredentialsError::new(format!("Cannot resolve assumed role for [ {} ].", profile_name))
+                })
+            }
+            None => self.static_credentials.get(profile_name).cloned().ok_or_else(|| {
+                CredentialsError::new(format!("No credentials found for profile [ {} ].", profile_name))
+            }),
+        }
+    }
+}
+
+fn is_near_expiry(credentials: &AwsCredentials) -> bool {
+    // Anonymous placeholders have no Expiration and never need refreshing.
+    if is_anonymous(credentials) {
+        return false;
+    }
+
+    match credentials.expires_at() {
+        Some(expiration) => *expiration - Utc::now() < ChronoDuration::seconds(EXPIRATION_SKEW_SECONDS),
+        None => false,
+    }
+}
+
+fn extract_dynamic_sources(
+    profile_configs: &HashMap<String, HashMap<String, String>>,
+    sso_sessions: &HashMap<String, HashMap<String, String>>,
+) -> HashMap<String, DynamicSource> {
+    profile_configs
+        .iter()
+        .filter_map(|(profile_name, config)| {
+            if let Some(role_arn) = config.get("role_arn") {
+                let source_profile = config.get("source_profile")?.clone();
+                return Some((
+                    profile_name.clone(),
+                    DynamicSource::RoleAssumption(RoleAssumption {
+                        role_arn: role_arn.clone(),
+                        source_profile,
+                        mfa_serial: config.get("mfa_serial").cloned(),
+                        external_id: config.get("external_id").cloned(),
+                        role_session_name: config.get("role_session_name").cloned(),
+                        duration_seconds: config.get("duration_seconds").and_then(|v| v.parse().ok()),
+                    }),
+                ));
+            }
+
+            if let Some(sso_profile) = extract_sso_profile(config, sso_sessions) {
+                return Some((profile_name.clone(), DynamicSource::Sso(sso_profile)));
+            }
+
+            config
+                .get("credential_process")
+                .map(|command| (profile_name.clone(), DynamicSource::CredentialProcess(command.clone())))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn credentials_for_returns_static_profile() {
+        let mut static_credentials = HashMap::new();
+        static_credentials.insert(
+            "default".to_string(),
+            AwsCredentials::new("access_key", "secret_key", None, None),
+        );
+
+        let provider =
+            AutoRefreshingProfileCredentials::new(static_credentials, &HashMap::new(), &HashMap::new());
+        let credentials = provider.credentials_for("default").unwrap();
+
+        assert_eq!(credentials.aws_access_key_id(), "access_key");
+    }
+
+    #[test]
+    fn credentials_for_errors_on_unknown_profile() {
+        let provider = AutoRefreshingProfileCredentials::new(HashMap::new(), &HashMap::new(), &HashMap::new());
+        assert!(provider.credentials_for("missing").is_err());
+    }
+
+    #[test]
+    fn new_collects_every_role_assumption_not_just_one_per_profile() {
+        // `resolve`'s RoleAssumption branch needs the full set of role
+        // assumptions to follow a chained source_profile, not a map
+        // containing only the selected profile's own entry.
+        let mut profile_configs = HashMap::new();
+
+        let mut middle_config = HashMap::new();
+        middle_config.insert("role_arn".to_string(), "arn:aws:iam::111111111111:role/middle".to_string());
+        middle_config.insert("source_profile".to_string(), "base".to_string());
+        profile_configs.insert("middle".to_string(), middle_config);
+
+        let mut leaf_config = HashMap::new();
+        leaf_config.insert("role_arn".to_string(), "arn:aws:iam::111111111111:role/leaf".to_string());
+        leaf_config.insert("source_profile".to_string(), "middle".to_string());
+        profile_configs.insert("leaf".to_string(), leaf_config);
+
+        let provider = AutoRefreshingProfileCredentials::new(HashMap::new(), &profile_configs, &HashMap::new());
+
+        assert!(provider.role_assumptions.contains_key("leaf"));
+        assert!(provider.role_assumptions.contains_key("middle"));
+    }
+}
@@ -0,0 +1,83 @@
+use rusoto_credential::AwsCredentials;
+
+#[derive(Debug, Clone, Default)]
+pub struct AwsProfileCredential {
+    pub profile_name: Option<String>,
+    pub access_key: Option<String>,
+    pub secret_key: Option<String>,
+    pub token: Option<String>,
+}
+
+impl AwsProfileCredential {
+    pub fn new_with_profile_name(profile_name: String) -> Self {
+        AwsProfileCredential {
+            profile_name: Some(profile_name),
+            ..AwsProfileCredential::default()
+        }
+    }
+
+    /// Resolves this profile into a concrete `AwsCredentials` from its inline
+    /// access/secret keys. A profile with no inline keys — whether it carries
+    /// none intentionally (public-resource access) or defines a dynamic
+    /// source resolved elsewhere (`credential_process`, assume-role, SSO) —
+    /// is never dropped: it comes back as an anonymous placeholder (see
+    /// `is_anonymous`) so it still surfaces in the returned map for those
+    /// later, lazy resolution passes to find by profile name.
+    pub fn into_aws_credential(self) -> AwsCredentials {
+        if let (Some(access_key), Some(secret_key)) = (self.access_key, self.secret_key) {
+            return AwsCredentials::new(access_key, secret_key, self.token, None);
+        }
+
+        anonymous_aws_credential()
+    }
+}
+
+/// An `AwsCredentials` with no access key or secret key, used to represent
+/// profiles that intentionally carry no keys (public-resource access) or
+/// whose keys could not be resolved during the static parse pass.
+fn anonymous_aws_credential() -> AwsCredentials {
+    AwsCredentials::new(String::new(), String::new(), None, None)
+}
+
+/// Whether `credentials` is the anonymous placeholder produced by
+/// `AwsProfileCredential::into_aws_credential`.
+pub fn is_anonymous(credentials: &AwsCredentials) -> bool {
+    credentials.aws_access_key_id().is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn into_aws_credential_prefers_inline_keys() {
+        let aws_profile_credential = AwsProfileCredential {
+            access_key: Some("foo".to_string()),
+            secret_key: Some("bar".to_string()),
+            ..AwsProfileCredential::new_with_profile_name("default".to_string())
+        };
+
+        let credentials = aws_profile_credential.into_aws_credential();
+        assert_eq!(credentials.aws_access_key_id(), "foo");
+        assert!(!is_anonymous(&credentials));
+    }
+
+    #[test]
+    fn into_aws_credential_returns_anonymous_without_keys() {
+        let aws_profile_credential = AwsProfileCredential::new_with_profile_name("public".to_string());
+
+        let credentials = aws_profile_credential.into_aws_credential();
+        assert!(is_anonymous(&credentials));
+    }
+
+    #[test]
+    fn into_aws_credential_returns_anonymous_without_secret_key() {
+        let aws_profile_credential = AwsProfileCredential {
+            access_key: Some("foo".to_string()),
+            ..AwsProfileCredential::new_with_profile_name("partial".to_string())
+        };
+
+        let credentials = aws_profile_credential.into_aws_credential();
+        assert!(is_anonymous(&credentials));
+    }
+}
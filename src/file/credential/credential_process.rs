@@ -0,0 +1,158 @@
+use std::process::Command;
+
+use chrono::{DateTime, Utc};
+use rusoto_credential::{AwsCredentials, CredentialsError};
+use serde::Deserialize;
+
+const SUPPORTED_VERSION: u32 = 1;
+
+/// Matches the AWS CLI credential-process JSON schema documented at
+/// https://docs.aws.amazon.com/cli/latest/userguide/cli-configure-sourcing-external.html
+#[derive(Debug, Deserialize)]
+struct CredentialProcessOutput {
+    #[serde(rename = "Version")]
+    version: u32,
+    #[serde(rename = "AccessKeyId")]
+    access_key_id: String,
+    #[serde(rename = "SecretAccessKey")]
+    secret_access_key: String,
+    #[serde(rename = "SessionToken")]
+    session_token: Option<String>,
+    #[serde(rename = "Expiration")]
+    expiration: Option<DateTime<Utc>>,
+}
+
+/// Spawns the given `credential_process` command and parses its stdout into
+/// `AwsCredentials`.
+pub fn resolve_credential_process(command: &str) -> Result<AwsCredentials, CredentialsError> {
+    let words = split_shell_words(command).ok_or_else(|| {
+        CredentialsError::new(format!("Cannot tokenize credential_process command: [ {} ]", command))
+    })?;
+
+    let (program, args) = words
+        .split_first()
+        .ok_or_else(|| CredentialsError::new("credential_process command is empty."))?;
+
+    let output = Command::new(program).args(args).output().map_err(|e| {
+        CredentialsError::new(format!("Failed to run credential_process [ {} ]: {}", command, e))
+    })?;
+
+    if !output.status.success() {
+        return Err(CredentialsError::new(format!(
+            "credential_process [ {} ] exited with {}: {}",
+            command,
+            output.status,
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let parsed: CredentialProcessOutput = serde_json::from_str(&stdout).map_err(|e| {
+        CredentialsError::new(format!("Cannot parse credential_process output: {}", e))
+    })?;
+
+    if parsed.version != SUPPORTED_VERSION {
+        return Err(CredentialsError::new(format!(
+            "Unsupported credential_process Version: {}. Only Version 1 is supported.",
+            parsed.version
+        )));
+    }
+
+    Ok(AwsCredentials::new(
+        parsed.access_key_id,
+        parsed.secret_access_key,
+        parsed.session_token,
+        parsed.expiration,
+    ))
+}
+
+/// Splits a command line into shell-like words, honouring single and double quotes
+/// and backslash escapes.
+fn split_shell_words(command: &str) -> Option<Vec<String>> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+    let mut chars = command.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => current.push(c),
+            None => match c {
+                '\'' | '"' => {
+                    quote = Some(c);
+                    in_word = true;
+                }
+                '\\' => {
+                    if let Some(next) = chars.next() {
+                        current.push(next);
+                        in_word = true;
+                    }
+                }
+                c if c.is_whitespace() => {
+                    if in_word {
+                        words.push(std::mem::take(&mut current));
+                        in_word = false;
+                    }
+                }
+                _ => {
+                    current.push(c);
+                    in_word = true;
+                }
+            },
+        }
+    }
+
+    if quote.is_some() {
+        return None;
+    }
+
+    if in_word {
+        words.push(current);
+    }
+
+    Some(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_credential_process_parses_output() {
+        // The unescaped quotes are stripped by `split_shell_words`; escape them
+        // so `echo` actually emits valid JSON (quoted keys) for `serde_json` to parse.
+        let command = r#"echo {\"Version\":1,\"AccessKeyId\":\"foo\",\"SecretAccessKey\":\"bar\",\"SessionToken\":\"baz\"}"#;
+        let result = resolve_credential_process(command);
+        assert!(result.is_ok());
+
+        let credentials = result.unwrap();
+        assert_eq!(credentials.aws_access_key_id(), "foo");
+        assert_eq!(credentials.aws_secret_access_key(), "bar");
+    }
+
+    #[test]
+    fn resolve_credential_process_rejects_unsupported_version() {
+        let command = r#"echo {\"Version\":2,\"AccessKeyId\":\"foo\",\"SecretAccessKey\":\"bar\"}"#;
+        let result = resolve_credential_process(command);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn resolve_credential_process_rejects_non_zero_exit() {
+        let result = resolve_credential_process("false");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn split_shell_words_handles_quotes() {
+        let words = split_shell_words(r#"foo "bar baz" 'qux'"#).unwrap();
+        assert_eq!(words, vec!["foo", "bar baz", "qux"]);
+    }
+
+    #[test]
+    fn split_shell_words_rejects_unterminated_quote() {
+        assert!(split_shell_words(r#"foo "bar"#).is_none());
+    }
+}
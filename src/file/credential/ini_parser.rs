@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use rusoto_credential::CredentialsError;
+
+/// A single section (e.g. `[default]`, `[profile foo]`, `[sso-session foo]`)
+/// as parsed from an AWS-style credentials/config file.
+///
+/// AWS also lets a parent key's settings be written as an indented nested
+/// subsection instead of their own top-level section, e.g.:
+///
+/// ```ini
+/// [profile dev]
+/// sso_session = my-sso
+///   sso_region = us-east-1
+///   sso_start_url = https://my-sso-portal.awsapps.com/start
+/// ```
+///
+/// The indented `sso_region`/`sso_start_url` lines above are captured in
+/// `nested`, keyed by the parent line's value (`my-sso`), so a reference like
+/// `sso_session = my-sso` can be resolved inline without also requiring a
+/// separate `[sso-session my-sso]` section.
+#[derive(Debug, Clone, Default)]
+pub struct ProfileSection {
+    pub values: HashMap<String, String>,
+    pub nested: HashMap<String, HashMap<String, String>>,
+}
+
+/// Parses an AWS-style INI file into a map of section name to `ProfileSection`.
+///
+/// This preserves the original case of values, matches keys
+/// case-insensitively, trims surrounding quotes and whitespace around `=`,
+/// groups indented nested subsections under the parent key's value (see
+/// `ProfileSection::nested`), and never panics on a malformed file.
+pub fn parse_ini_sections(file_path: &Path) -> Result<HashMap<String, ProfileSection>, CredentialsError> {
+    let contents = fs::read_to_string(file_path)
+        .map_err(|e| CredentialsError::new(format!("Cannot read INI file [ {:?} ]: {}", file_path, e)))?;
+
+    Ok(parse_ini_sections_from_str(&contents))
+}
+
+fn parse_ini_sections_from_str(contents: &str) -> HashMap<String, ProfileSection> {
+    let mut sections: HashMap<String, ProfileSection> = HashMap::new();
+    let mut current_section: Option<String> = None;
+    let mut current_parent_value: Option<String> = None;
+
+    for raw_line in contents.lines() {
+        let trimmed = raw_line.trim();
+
+        if trimmed.is_empty() || trimmed.starts_with(';') || trimmed.starts_with('#') {
+            continue;
+        }
+
+        if let Some(section_name) = parse_section_header(trimmed) {
+            current_section = Some(section_name.to_string());
+            current_parent_value = None;
+            sections.entry(section_name.to_string()).or_default();
+            continue;
+        }
+
+        let section_name = match &current_section {
+            Some(section_name) => section_name.clone(),
+            None => continue,
+        };
+
+        let (key, value) = match split_key_value(trimmed) {
+            Some(pair) => pair,
+            None => continue,
+        };
+
+        let key = key.trim().to_ascii_lowercase();
+        let value = trim_quotes(value.trim()).to_string();
+        let is_indented = raw_line.starts_with(' ') || raw_line.starts_with('\t');
+
+        let section = sections.entry(section_name).or_default();
+
+        if is_indented {
+            if let Some(parent_value) = &current_parent_value {
+                section.nested.entry(parent_value.clone()).or_default().insert(key, value);
+                continue;
+            }
+        }
+
+        current_parent_value = Some(value.clone());
+        section.values.insert(key, value);
+    }
+
+    sections
+}
+
+fn parse_section_header(trimmed: &str) -> Option<&str> {
+    let inner = trimmed.strip_prefix('[')?.strip_suffix(']')?;
+    Some(inner.trim())
+}
+
+fn split_key_value(trimmed: &str) -> Option<(&str, &str)> {
+    trimmed.split_once('=')
+}
+
+fn trim_quotes(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(stripped) = value.strip_prefix(quote).and_then(|v| v.strip_suffix(quote)) {
+            return stripped;
+        }
+    }
+
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trim_quotes_strips_matching_quotes() {
+        assert_eq!(trim_quotes("\"foo\""), "foo");
+        assert_eq!(trim_quotes("'foo'"), "foo");
+        assert_eq!(trim_quotes("foo"), "foo");
+    }
+
+    #[test]
+    fn parse_ini_sections_from_str_reads_flat_keys() {
+        let sections = parse_ini_sections_from_str(
+            "[default]\naws_access_key_id = foo\naws_secret_access_key = bar\n",
+        );
+
+        let default_section = sections.get("default").unwrap();
+        assert_eq!(default_section.values.get("aws_access_key_id"), Some(&"foo".to_string()));
+        assert_eq!(
+            default_section.values.get("aws_secret_access_key"),
+            Some(&"bar".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_ini_sections_from_str_groups_indented_nested_subsections() {
+        let sections = parse_ini_sections_from_str(concat!(
+            "[profile dev]\n",
+            "sso_session = my-sso\n",
+            "  sso_region = us-east-1\n",
+            "  sso_start_url = https://example.awsapps.com/start\n",
+            "region = us-west-2\n",
+        ));
+
+        let profile = sections.get("profile dev").unwrap();
+        assert_eq!(profile.values.get("sso_session"), Some(&"my-sso".to_string()));
+        assert_eq!(profile.values.get("region"), Some(&"us-west-2".to_string()));
+
+        let nested = profile.nested.get("my-sso").expect("No nested sso_session block");
+        assert_eq!(nested.get("sso_region"), Some(&"us-east-1".to_string()));
+        assert_eq!(
+            nested.get("sso_start_url"),
+            Some(&"https://example.awsapps.com/start".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_ini_sections_from_str_ignores_lines_before_first_section() {
+        let sections = parse_ini_sections_from_str("stray_key = stray_value\n[default]\nregion = us-east-1\n");
+
+        assert_eq!(sections.len(), 1);
+        assert_eq!(
+            sections.get("default").unwrap().values.get("region"),
+            Some(&"us-east-1".to_string())
+        );
+    }
+}
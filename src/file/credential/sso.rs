@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use chrono::{DateTime, TimeZone, Utc};
+use dirs::home_dir;
+use rusoto_core::Region;
+use rusoto_credential::{AwsCredentials, CredentialsError};
+use rusoto_sso::{GetRoleCredentialsRequest, Sso, SsoClient};
+use serde::Deserialize;
+
+#[derive(Debug, Clone)]
+pub(crate) struct SsoProfile {
+    start_url: String,
+    region: String,
+    account_id: String,
+    role_name: String,
+}
+
+/// The subset of a cached `~/.aws/sso/cache/*.json` token this module reads.
+#[derive(Debug, Deserialize)]
+struct CachedSsoToken {
+    #[serde(rename = "startUrl")]
+    start_url: String,
+    #[serde(rename = "accessToken")]
+    access_token: String,
+    #[serde(rename = "expiresAt")]
+    expires_at: Option<String>,
+}
+
+/// Extracts the AWS SSO (IAM Identity Center) configuration for a single
+/// profile, either from inline `sso_*` keys or a referenced `sso_session`
+/// block in `sso_sessions`, without resolving it. Resolution (the network
+/// call to `GetRoleCredentials`) happens lazily, once per selected profile,
+/// via `resolve_sso_profile`.
+pub(crate) fn extract_sso_profile(
+    config: &HashMap<String, String>,
+    sso_sessions: &HashMap<String, HashMap<String, String>>,
+) -> Option<SsoProfile> {
+    let session = config.get("sso_session").and_then(|name| sso_sessions.get(name));
+
+    let start_url = config
+        .get("sso_start_url")
+        .or_else(|| session.and_then(|s| s.get("sso_start_url")))?
+        .clone();
+    let region = config
+        .get("sso_region")
+        .or_else(|| session.and_then(|s| s.get("sso_region")))?
+        .clone();
+    let account_id = config.get("sso_account_id")?.clone();
+    let role_name = config.get("sso_role_name")?.clone();
+
+    Some(SsoProfile {
+        start_url,
+        region,
+        account_id,
+        role_name,
+    })
+}
+
+/// Resolves a single SSO profile against its cached access token, via the
+/// `GetRoleCredentials` API. This makes a network call, so callers resolve it
+/// lazily for the one profile actually selected, not for every SSO profile in
+/// the file up front.
+pub(crate) fn resolve_sso_profile(profile: &SsoProfile) -> Result<AwsCredentials, CredentialsError> {
+    let token = read_cached_sso_token(&profile.start_url)?;
+
+    let region = Region::from_str(&profile.region)
+        .map_err(|e| CredentialsError::new(format!("Invalid sso_region [ {} ]: {}", profile.region, e)))?;
+
+    let client = SsoClient::new(region);
+
+    let request = GetRoleCredentialsRequest {
+        access_token: token.access_token,
+        account_id: profile.account_id.clone(),
+        role_name: profile.role_name.clone(),
+    };
+
+    let runtime = tokio::runtime::Runtime::new()
+        .map_err(|e| CredentialsError::new(format!("Cannot create async runtime: {}", e)))?;
+
+    let response = runtime
+        .block_on(client.get_role_credentials(request))
+        .map_err(|e| CredentialsError::new(format!("GetRoleCredentials failed: {}", e)))?;
+
+    let role_credentials = response.role_credentials.ok_or_else(|| {
+        CredentialsError::new("GetRoleCredentials response did not contain credentials.")
+    })?;
+
+    let expiration = Utc.timestamp_millis_opt(role_credentials.expiration).single();
+
+    Ok(AwsCredentials::new(
+        role_credentials.access_key_id.unwrap_or_default(),
+        role_credentials.secret_access_key.unwrap_or_default(),
+        role_credentials.session_token,
+        expiration,
+    ))
+}
+
+/// Scans `~/.aws/sso/cache/*.json` for a cached token whose `startUrl`
+/// matches and that has not yet expired.
+fn read_cached_sso_token(start_url: &str) -> Result<CachedSsoToken, CredentialsError> {
+    let cache_dir = sso_cache_dir()?;
+
+    let entries = fs::read_dir(&cache_dir).map_err(|e| {
+        CredentialsError::new(format!("Cannot read SSO cache directory [ {:?} ]: {}", cache_dir, e))
+    })?;
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+            continue;
+        }
+
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(_) => continue,
+        };
+
+        let token: CachedSsoToken = match serde_json::from_str(&contents) {
+            Ok(token) => token,
+            Err(_) => continue,
+        };
+
+        if token.start_url == start_url && !is_token_expired(&token) {
+            return Ok(token);
+        }
+    }
+
+    Err(CredentialsError::new(format!(
+        "No valid cached SSO token found for [ {} ]. Run `aws sso login` to log in.",
+        start_url
+    )))
+}
+
+fn is_token_expired(token: &CachedSsoToken) -> bool {
+    match token
+        .expires_at
+        .as_deref()
+        .and_then(|value| DateTime::parse_from_rfc3339(value).ok())
+    {
+        Some(expires_at) => expires_at.with_timezone(&Utc) <= Utc::now(),
+        None => false,
+    }
+}
+
+fn sso_cache_dir() -> Result<PathBuf, CredentialsError> {
+    home_dir()
+        .map(|home| home.join(".aws").join("sso").join("cache"))
+        .ok_or_else(|| CredentialsError::new("Cannot determine home directory for SSO cache lookup."))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_sso_profile_reads_inline_keys() {
+        let mut config = HashMap::new();
+        config.insert("sso_start_url".to_string(), "https://example.awsapps.com/start".to_string());
+        config.insert("sso_region".to_string(), "us-east-1".to_string());
+        config.insert("sso_account_id".to_string(), "111111111111".to_string());
+        config.insert("sso_role_name".to_string(), "Admin".to_string());
+
+        let profile = extract_sso_profile(&config, &HashMap::new());
+        assert!(profile.is_some());
+        assert_eq!(profile.unwrap().role_name, "Admin");
+    }
+
+    #[test]
+    fn extract_sso_profile_falls_back_to_sso_session() {
+        let mut config = HashMap::new();
+        config.insert("sso_session".to_string(), "my-session".to_string());
+        config.insert("sso_account_id".to_string(), "111111111111".to_string());
+        config.insert("sso_role_name".to_string(), "Admin".to_string());
+
+        let mut session = HashMap::new();
+        session.insert("sso_start_url".to_string(), "https://example.awsapps.com/start".to_string());
+        session.insert("sso_region".to_string(), "us-east-1".to_string());
+
+        let mut sso_sessions = HashMap::new();
+        sso_sessions.insert("my-session".to_string(), session);
+
+        let profile = extract_sso_profile(&config, &sso_sessions);
+        assert!(profile.is_some());
+        assert_eq!(profile.unwrap().start_url, "https://example.awsapps.com/start");
+    }
+
+    #[test]
+    fn extract_sso_profile_returns_none_without_sso_keys() {
+        let config = HashMap::new();
+        assert!(extract_sso_profile(&config, &HashMap::new()).is_none());
+    }
+
+    #[test]
+    fn read_cached_sso_token_errors_without_matching_token() {
+        let result = read_cached_sso_token("https://does-not-exist.awsapps.com/start");
+        assert!(result.is_err());
+    }
+}